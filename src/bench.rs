@@ -8,10 +8,12 @@ use std::{
 use crossbeam_channel::Sender;
 use thiserror::Error;
 
-use crate::{input, BenchError, BenchResult, TracingAlloc};
+use crate::{black_box, input, BenchError, BenchResult, TracingAlloc};
 
 pub mod simple;
 
+pub(crate) mod detailed;
+
 pub type Function = for<'a> fn(&'a str, Bench) -> BenchResult;
 
 #[derive(Debug, Error)]
@@ -24,16 +26,47 @@ pub struct MemoryBenchError {
 
 #[derive(Default)]
 pub(crate) struct RuntimeData {
-    // pub(crate) total_runs: u32,
-    // pub(crate) min_run: Duration,
+    pub(crate) total_runs: u32,
+    pub(crate) min_run: Duration,
     pub(crate) mean_run: Duration,
-    // pub(crate) max_run: Duration,
+    pub(crate) max_run: Duration,
+    pub(crate) median_run: Duration,
+    /// 95% confidence interval for `mean_run`, as `(lower, upper)`.
+    pub(crate) mean_ci_95: (Duration, Duration),
+}
+
+/// Z-score for a two-sided 95% confidence interval under the normal approximation.
+const Z_95: f64 = 1.959_964_1;
+
+fn median_run(samples: &mut [Duration]) -> Duration {
+    samples.sort_unstable();
+    samples[samples.len() / 2]
+}
+
+/// Computes a 95% confidence interval for the true mean of `samples` analytically, via the
+/// normal approximation `mean ± z * (stddev / sqrt(n))`, in O(n) over the full sample.
+/// Bootstrapping by resampling would need `samples.len()` draws per resample to stay
+/// statistically honest, which is too expensive for the millions of samples a fast
+/// day/part collects inside the benching window; the analytic interval scales for free.
+fn mean_ci_95(samples: &[Duration]) -> (Duration, Duration) {
+    let n = samples.len() as f64;
+    let nanos: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+
+    let mean = nanos.iter().sum::<f64>() / n;
+    let variance = nanos.iter().map(|&x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0);
+    let margin = Z_95 * (variance / n).sqrt();
+
+    let lower = Duration::from_nanos((mean - margin).max(0.0) as u64);
+    let upper = Duration::from_nanos((mean + margin).max(0.0) as u64);
+
+    (lower, upper)
 }
 
 #[derive(Default)]
 pub(crate) struct MemoryData {
-    // pub(crate) end_ts: u128,
-    // pub(crate) graph_points: Vec<(f64, f64)>,
+    pub(crate) end_ts: u128,
+    pub(crate) graph_points: Vec<(f64, f64)>,
+    pub(crate) num_allocs: usize,
     pub(crate) max_memory: usize,
 }
 
@@ -41,7 +74,8 @@ fn get_data(trace_input: &str) -> MemoryData {
     let mut points = Vec::new();
     let mut cur_bytes = 0;
     let mut prev_bytes = 0;
-    // let mut end_ts = 0;
+    let mut end_ts = 0;
+    let mut num_allocs = 0;
     let mut max_bytes = 0;
 
     for line in trace_input.lines() {
@@ -52,11 +86,14 @@ fn get_data(trace_input: &str) -> MemoryData {
             parts.next().map(str::parse),
             parts.next().map(str::parse),
         ) {
-            (Some("A"), Some(Ok(ts)), Some(Ok(size))) => (size, ts),
+            (Some("A"), Some(Ok(ts)), Some(Ok(size))) => {
+                num_allocs += 1;
+                (size, ts)
+            }
             (Some("F"), Some(Ok(ts)), Some(Ok(size))) => (-size, ts),
             (Some("S"), Some(Ok(ts)), _) => (0, ts),
             (Some("E"), Some(Ok(ts)), _) => {
-                // end_ts = ts;
+                end_ts = ts;
                 (0, ts)
             }
             _ => {
@@ -74,60 +111,63 @@ fn get_data(trace_input: &str) -> MemoryData {
     }
 
     MemoryData {
-        // end_ts,
-        // graph_points: points,
+        end_ts,
+        graph_points: points,
+        num_allocs,
         max_memory: max_bytes as usize,
     }
 }
 
 fn bench_function_runtime<Output, OutputErr>(
     bench_time: u64,
-    func: impl Fn() -> Result<Output, OutputErr>,
+    input: &str,
+    func: impl Fn(&str) -> Result<Output, OutputErr>,
 ) -> RuntimeData {
-    let mut total_time = Duration::default();
-    let mut total_runs = 0;
-    let mut min_run = Duration::from_secs(u64::MAX);
-    let mut max_run = Duration::default();
+    let mut samples = Vec::new();
     let bench_start = Instant::now();
 
     loop {
         let start = Instant::now();
-        let res = func();
+        // Route the input through `black_box` too, so the optimizer can't prove `func`'s
+        // argument is loop-invariant and hoist the (otherwise pure) call out of the loop.
+        let res = func(black_box(input));
         let elapsed = start.elapsed();
-        total_time += start.elapsed();
-        total_runs += 1;
+        samples.push(elapsed);
 
         // Don't drop while measuring, in case the user returns a non-trivial type.
         // Also don't handle errors, as the function is assumed to be pure, and has already
         // had its return value checked in our caller.
-        drop(res);
-
-        if elapsed < min_run {
-            min_run = elapsed;
-        }
-
-        if elapsed > max_run {
-            max_run = elapsed;
-        }
+        //
+        // Route the result through `black_box` before dropping it, so a pure, cheap
+        // solution can't have its call elided entirely.
+        drop(black_box(res));
 
-        if bench_start.elapsed().as_secs() >= bench_time && total_runs >= 10 {
+        if bench_start.elapsed().as_secs() >= bench_time && samples.len() >= 10 {
             break;
         }
     }
 
-    let mean_run = total_time / total_runs;
+    let total_runs = samples.len() as u32;
+    let min_run = samples.iter().copied().min().unwrap_or_default();
+    let max_run = samples.iter().copied().max().unwrap_or_default();
+    let mean_run = samples.iter().sum::<Duration>() / total_runs;
+    let median_run = median_run(&mut samples);
+    let mean_ci_95 = mean_ci_95(&samples);
 
     RuntimeData {
-        // total_runs,
-        // min_run,
+        total_runs,
+        min_run,
         mean_run,
-        // max_run,
+        max_run,
+        median_run,
+        mean_ci_95,
     }
 }
 
 fn bench_function_memory<Output, OutputErr>(
     alloc: &TracingAlloc,
-    func: impl Fn() -> Result<Output, OutputErr>,
+    input: &str,
+    func: impl Fn(&str) -> Result<Output, OutputErr>,
 ) -> Result<MemoryData, MemoryBenchError> {
     let trace_file = tempfile::tempfile()?;
 
@@ -138,7 +178,7 @@ fn bench_function_memory<Output, OutputErr>(
     alloc.enable_tracing();
     // Don't discard here, or dropping the return value will be caught
     // by the tracer.
-    let res = func();
+    let res = func(input);
     alloc.disable_tracing();
     let _ = res;
 
@@ -173,9 +213,10 @@ pub struct Bench {
 impl Bench {
     pub fn bench<T: Display, E: Display>(
         self,
-        f: impl Fn() -> Result<T, E> + Copy,
+        input: &str,
+        f: impl Fn(&str) -> Result<T, E> + Copy,
     ) -> Result<(), BenchError> {
-        match f() {
+        match f(input) {
             Ok(t) => {
                 self.chan
                     .send(BenchEvent::Answer {
@@ -185,14 +226,14 @@ impl Bench {
                     .map_err(|_| BenchError::ChannelError(self.id))?;
 
                 if !self.run_only {
-                    let data = bench_function_memory(self.alloc, f)
+                    let data = bench_function_memory(self.alloc, input, f)
                         .map_err(|e| BenchError::MemoryBenchError(e, self.id))?;
 
                     self.chan
                         .send(BenchEvent::Memory { data, id: self.id })
                         .map_err(|_| BenchError::ChannelError(self.id))?;
 
-                    let data = bench_function_runtime(self.bench_time, f);
+                    let data = bench_function_runtime(self.bench_time, input, f);
                     self.chan
                         .send(BenchEvent::Timing { data, id: self.id })
                         .map_err(|_| BenchError::ChannelError(self.id))?;
@@ -254,3 +295,48 @@ pub(crate) fn bench_worker(year: u16, day: u8, bench: Bench, func: Function) {
         .send(BenchEvent::Finish { id })
         .expect("Unable to send finish");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_run_picks_the_middle_sample() {
+        let mut samples = vec![
+            Duration::from_millis(3),
+            Duration::from_millis(1),
+            Duration::from_millis(2),
+        ];
+        assert_eq!(median_run(&mut samples), Duration::from_millis(2));
+    }
+
+    #[test]
+    fn mean_ci_95_collapses_to_the_mean_for_identical_samples() {
+        let samples = vec![Duration::from_millis(10); 50];
+        let (lower, upper) = mean_ci_95(&samples);
+        assert_eq!(lower, Duration::from_millis(10));
+        assert_eq!(upper, Duration::from_millis(10));
+    }
+
+    #[test]
+    fn mean_ci_95_brackets_the_sample_mean() {
+        let samples: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        let mean_nanos =
+            samples.iter().map(Duration::as_nanos).sum::<u128>() / samples.len() as u128;
+        let (lower, upper) = mean_ci_95(&samples);
+
+        assert!(lower.as_nanos() < mean_nanos);
+        assert!(upper.as_nanos() > mean_nanos);
+    }
+
+    #[test]
+    fn mean_ci_95_narrows_as_sample_count_grows() {
+        let small: Vec<Duration> = (0..10).map(Duration::from_millis).collect();
+        let large: Vec<Duration> = (0..10_000).map(|i| Duration::from_millis(i % 10)).collect();
+
+        let small_width = mean_ci_95(&small).1.as_nanos() - mean_ci_95(&small).0.as_nanos();
+        let large_width = mean_ci_95(&large).1.as_nanos() - mean_ci_95(&large).0.as_nanos();
+
+        assert!(large_width < small_width);
+    }
+}
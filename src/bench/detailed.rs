@@ -0,0 +1,123 @@
+use std::{iter, path::Path, time::Duration};
+
+use crate::{
+    bench::{Bench, BenchEvent, MemoryData, RuntimeData},
+    export::{self, ExportRecord, OutputFormat},
+    graph, input, print_footer, print_header, render_decimal, render_duration, BenchError, Day,
+    TracingAlloc, UserError,
+};
+
+/// Runs `days` with full memory and timing instrumentation, rendering a per-part
+/// allocation timeline (sparkline, plus an optional SVG dump) alongside the answer and
+/// mean run time. Backs `RunType::Detailed`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_detailed_bench(
+    alloc: &'static TracingAlloc,
+    year: u16,
+    days: &[&Day],
+    bench_time: u64,
+    graph_dir: Option<&Path>,
+    output: Option<OutputFormat>,
+    output_file: Option<&Path>,
+    baseline: Option<&[ExportRecord]>,
+    regression_threshold: f64,
+) -> Result<(), BenchError> {
+    print_header();
+
+    let mut total_time = Duration::ZERO;
+    let mut records = Vec::new();
+
+    for &day in days {
+        let parts = iter::once(day.part_1).chain(day.part_2).zip(1..);
+
+        for (part, part_id) in parts {
+            let (sender, receiver) = crossbeam_channel::unbounded();
+
+            let bench = Bench {
+                alloc,
+                id: 0,
+                chan: sender,
+                run_only: false,
+                bench_time,
+            };
+
+            let day_input = input(year, day.day).open()?;
+            part(&day_input, bench)?;
+
+            let mut answer = String::new();
+            let mut memory: Option<MemoryData> = None;
+            let mut timing: Option<RuntimeData> = None;
+
+            for event in receiver.iter() {
+                match event {
+                    BenchEvent::Answer { answer: msg, .. }
+                    | BenchEvent::Error { err: msg, .. } => answer = msg,
+                    BenchEvent::Memory { data, .. } => memory = Some(data),
+                    BenchEvent::Timing { data, .. } => timing = Some(data),
+                    BenchEvent::Finish { .. } => break,
+                }
+            }
+
+            let time = timing.as_ref().map_or(Duration::ZERO, |t| t.mean_run);
+            total_time += time;
+
+            let time_col = match &timing {
+                Some(t) => format!(
+                    "{} (95% CI {} – {})",
+                    render_duration(t.mean_run),
+                    render_duration(t.mean_ci_95.0),
+                    render_duration(t.mean_ci_95.1)
+                ),
+                None => render_duration(time),
+            };
+
+            let max_memory = memory.as_ref().map_or(0, |m| m.max_memory);
+            let allocs = memory.as_ref().map_or(0, |m| m.num_allocs);
+            let sparkline = memory.as_ref().map_or(String::new(), graph::render_sparkline);
+
+            println!(
+                "  {:>2}.{} | {:<30} | {:<32} | {:>7} | {} | {}",
+                day.day,
+                part_id,
+                answer,
+                time_col,
+                render_decimal(allocs),
+                render_decimal(max_memory),
+                sparkline,
+            );
+
+            if let (Some(dir), Some(data)) = (graph_dir, memory.as_ref()) {
+                graph::write_svg(dir, day.day, part_id, data).map_err(UserError)?;
+            }
+
+            let record = ExportRecord {
+                day: day.day,
+                part: part_id,
+                answer,
+                mean_run_nanos: timing.as_ref().map_or(0, |t| t.mean_run.as_nanos()),
+                median_run_nanos: timing.as_ref().map_or(0, |t| t.median_run.as_nanos()),
+                min_run_nanos: timing.as_ref().map_or(0, |t| t.min_run.as_nanos()),
+                max_run_nanos: timing.as_ref().map_or(0, |t| t.max_run.as_nanos()),
+                allocs,
+                max_memory,
+            };
+
+            if let Some(baseline) = baseline {
+                if let Some(note) = export::render_regression(&record, baseline, regression_threshold)
+                {
+                    println!("         {note}");
+                }
+            }
+
+            records.push(record);
+        }
+    }
+
+    print_footer(total_time);
+
+    if let Some(format) = output {
+        export::export(&records, format, output_file).map_err(UserError)?;
+    }
+
+    Ok(())
+}
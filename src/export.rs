@@ -0,0 +1,209 @@
+//! Machine-readable export of `detailed` run results, so CI can diff a run against a
+//! stored baseline instead of only reading the aligned ASCII table. Only wired into
+//! `RunType::Detailed` for now — `run_simple_bench` (the plain `bench` subcommand) has no
+//! export path yet.
+
+use std::{fmt::Write as _, fs, io, path::Path, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    Json,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            other => Err(format!(
+                "unknown output format '{other}', expected 'json' or 'csv'"
+            )),
+        }
+    }
+}
+
+/// One day/part's benchmark results, in a form stable enough to diff across runs. Times
+/// are stored as integer nanoseconds rather than as `Duration`, so both the JSON and CSV
+/// encodings write them out exactly.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct ExportRecord {
+    pub(crate) day: u8,
+    pub(crate) part: usize,
+    pub(crate) answer: String,
+    pub(crate) mean_run_nanos: u128,
+    pub(crate) median_run_nanos: u128,
+    pub(crate) min_run_nanos: u128,
+    pub(crate) max_run_nanos: u128,
+    pub(crate) allocs: usize,
+    pub(crate) max_memory: usize,
+}
+
+impl ExportRecord {
+    fn key(&self) -> (u8, usize) {
+        (self.day, self.part)
+    }
+}
+
+/// Quotes a CSV field per RFC 4180: wrap in `"`, doubling any `"` already in the field.
+fn csv_field(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+fn render_csv(records: &[ExportRecord]) -> String {
+    let mut out = String::from(
+        "day,part,answer,mean_run_nanos,median_run_nanos,min_run_nanos,max_run_nanos,allocs,max_memory\n",
+    );
+
+    for r in records {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{}",
+            r.day,
+            r.part,
+            csv_field(&r.answer),
+            r.mean_run_nanos,
+            r.median_run_nanos,
+            r.min_run_nanos,
+            r.max_run_nanos,
+            r.allocs,
+            r.max_memory,
+        );
+    }
+
+    out
+}
+
+/// Writes `records` in `format` to `file`, or to stdout when no file is given.
+pub(crate) fn export(
+    records: &[ExportRecord],
+    format: OutputFormat,
+    file: Option<&Path>,
+) -> io::Result<()> {
+    let rendered = match format {
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(records).expect("ExportRecord always serializes")
+        }
+        OutputFormat::Csv => render_csv(records),
+    };
+
+    match file {
+        Some(path) => fs::write(path, rendered),
+        None => {
+            println!("{rendered}");
+            Ok(())
+        }
+    }
+}
+
+/// Loads a prior export written by `export(.., OutputFormat::Json, ..)`. Only the JSON
+/// shape round-trips, so `--baseline` always expects a file written with `--output json`.
+pub(crate) fn load_baseline(path: &Path) -> io::Result<Vec<ExportRecord>> {
+    let contents = fs::read_to_string(path)?;
+    serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Percent change of `new` relative to `old`, positive meaning `new` grew.
+fn percent_change(old: f64, new: f64) -> f64 {
+    if old == 0.0 {
+        0.0
+    } else {
+        (new - old) / old * 100.0
+    }
+}
+
+/// Finds `record`'s counterpart in `baseline` and renders its percent change in mean run
+/// time and peak memory, flagging the line as a regression once either exceeds
+/// `threshold_pct`. Returns `None` if the baseline has no matching day/part.
+pub(crate) fn render_regression(
+    record: &ExportRecord,
+    baseline: &[ExportRecord],
+    threshold_pct: f64,
+) -> Option<String> {
+    let base = baseline.iter().find(|b| b.key() == record.key())?;
+
+    let time_pct = percent_change(base.mean_run_nanos as f64, record.mean_run_nanos as f64);
+    let mem_pct = percent_change(base.max_memory as f64, record.max_memory as f64);
+    let regressed = time_pct > threshold_pct || mem_pct > threshold_pct;
+
+    Some(format!(
+        "time {time_pct:+.1}%, mem {mem_pct:+.1}%{}",
+        if regressed { "  [REGRESSION]" } else { "" }
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(day: u8, part: usize, mean_run_nanos: u128, max_memory: usize) -> ExportRecord {
+        ExportRecord {
+            day,
+            part,
+            answer: String::new(),
+            mean_run_nanos,
+            median_run_nanos: mean_run_nanos,
+            min_run_nanos: mean_run_nanos,
+            max_run_nanos: mean_run_nanos,
+            allocs: 0,
+            max_memory,
+        }
+    }
+
+    #[test]
+    fn csv_field_wraps_plain_values_in_quotes() {
+        assert_eq!(csv_field("42"), "\"42\"");
+    }
+
+    #[test]
+    fn csv_field_doubles_embedded_quotes() {
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn render_csv_quotes_the_answer_column() {
+        let mut answer_record = record(1, 1, 100, 10);
+        answer_record.answer = "42".to_owned();
+        let csv = render_csv(&[answer_record]);
+        assert!(csv.lines().nth(1).unwrap().starts_with("1,1,\"42\",100,"));
+    }
+
+    #[test]
+    fn percent_change_from_zero_baseline_is_zero() {
+        assert_eq!(percent_change(0.0, 100.0), 0.0);
+    }
+
+    #[test]
+    fn percent_change_reports_signed_growth() {
+        assert!((percent_change(100.0, 150.0) - 50.0).abs() < f64::EPSILON);
+        assert!((percent_change(100.0, 50.0) - -50.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn render_regression_returns_none_without_a_matching_baseline() {
+        let record = record(1, 1, 100, 10);
+        assert!(render_regression(&record, &[], 5.0).is_none());
+    }
+
+    #[test]
+    fn render_regression_flags_runs_past_the_threshold() {
+        let baseline = vec![record(1, 1, 100, 10)];
+        let record = record(1, 1, 200, 10);
+        assert!(render_regression(&record, &baseline, 5.0)
+            .unwrap()
+            .contains("[REGRESSION]"));
+    }
+
+    #[test]
+    fn render_regression_allows_runs_within_the_threshold() {
+        let baseline = vec![record(1, 1, 100, 10)];
+        let record = record(1, 1, 101, 10);
+        assert!(!render_regression(&record, &baseline, 5.0)
+            .unwrap()
+            .contains("[REGRESSION]"));
+    }
+}
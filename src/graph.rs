@@ -0,0 +1,143 @@
+//! Rendering of the per-day memory allocation timeline captured by `TracingAlloc`,
+//! used by `RunType::Detailed` to show allocation shape instead of just the peak.
+
+use std::{fmt::Write as _, fs, io, path::Path};
+
+use crate::{bench::MemoryData, render_decimal};
+
+const SPARKLINE_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const SPARKLINE_WIDTH: usize = 40;
+
+/// Resamples the step function in `data.graph_points` into `width` buckets, each holding
+/// the maximum live-byte value observed in that slice of the normalized timeline, so short
+/// spikes survive the downsampling instead of being averaged away.
+fn resample(data: &MemoryData, width: usize) -> Vec<f64> {
+    if data.graph_points.is_empty() || width == 0 {
+        return Vec::new();
+    }
+
+    let start_ts = data.graph_points[0].0;
+    let span = (data.end_ts as f64 - start_ts).max(1.0);
+
+    let mut buckets = vec![0f64; width];
+    let mut last_in_bucket: Vec<Option<f64>> = vec![None; width];
+    for &(ts, bytes) in &data.graph_points {
+        let frac = ((ts - start_ts) / span).clamp(0.0, 1.0);
+        let bucket = ((frac * width as f64) as usize).min(width - 1);
+        buckets[bucket] = buckets[bucket].max(bytes);
+        last_in_bucket[bucket] = Some(bytes);
+    }
+
+    // A bucket with no transition in it holds whatever was live when the previous one ended.
+    let mut live = 0f64;
+    for (bucket, last) in buckets.iter_mut().zip(last_in_bucket) {
+        match last {
+            Some(bytes) => live = bytes,
+            None => *bucket = live,
+        }
+    }
+
+    buckets
+}
+
+/// Renders a compact terminal sparkline of live memory use across `[start_ts, end_ts]`,
+/// followed by peak and steady-state (final live bytes) markers.
+pub(crate) fn render_sparkline(data: &MemoryData) -> String {
+    let buckets = resample(data, SPARKLINE_WIDTH);
+    if buckets.is_empty() {
+        return String::new();
+    }
+
+    let max = buckets.iter().copied().fold(0f64, f64::max).max(1.0);
+    let mut out: String = buckets
+        .iter()
+        .map(|&bytes| {
+            let level = ((bytes / max) * (SPARKLINE_LEVELS.len() - 1) as f64).round() as usize;
+            SPARKLINE_LEVELS[level.min(SPARKLINE_LEVELS.len() - 1)]
+        })
+        .collect();
+
+    let steady = data.graph_points.last().map_or(0.0, |p| p.1) as usize;
+    let _ = write!(
+        out,
+        "  peak {}B  end {}B",
+        render_decimal(data.max_memory),
+        render_decimal(steady)
+    );
+
+    out
+}
+
+/// Dumps `data`'s allocation timeline as a minimal standalone SVG line chart to
+/// `<dir>/day<day>_part<part>.svg` (`<day>` zero-padded to two digits), creating `dir` if it doesn't exist.
+pub(crate) fn write_svg(dir: &Path, day: u8, part: usize, data: &MemoryData) -> io::Result<()> {
+    const WIDTH: f64 = 800.0;
+    const HEIGHT: f64 = 200.0;
+
+    fs::create_dir_all(dir)?;
+
+    let start_ts = data.graph_points.first().map_or(0.0, |p| p.0);
+    let span = (data.end_ts as f64 - start_ts).max(1.0);
+    let max = (data.max_memory as f64).max(1.0);
+
+    let mut path = String::new();
+    for (i, &(ts, bytes)) in data.graph_points.iter().enumerate() {
+        let x = (ts - start_ts) / span * WIDTH;
+        let y = HEIGHT - bytes / max * HEIGHT;
+        let cmd = if i == 0 { "M" } else { "L" };
+        let _ = write!(path, "{cmd}{x:.2},{y:.2} ");
+    }
+
+    let svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {WIDTH} {HEIGHT}">
+  <rect width="{WIDTH}" height="{HEIGHT}" fill="white"/>
+  <path d="{path}" fill="none" stroke="black" stroke-width="1.5"/>
+</svg>
+"#
+    );
+
+    fs::write(dir.join(format!("day{day:02}_part{part}.svg")), svg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(points: Vec<(f64, f64)>, end_ts: u128) -> MemoryData {
+        let max_memory = points.iter().fold(0f64, |acc, &(_, bytes)| acc.max(bytes)) as usize;
+        MemoryData {
+            end_ts,
+            graph_points: points,
+            num_allocs: 0,
+            max_memory,
+        }
+    }
+
+    #[test]
+    fn resample_of_empty_points_is_empty() {
+        let data = data(Vec::new(), 0);
+        assert!(resample(&data, SPARKLINE_WIDTH).is_empty());
+    }
+
+    #[test]
+    fn resample_keeps_the_peak_in_its_bucket() {
+        let data = data(vec![(0.0, 0.0), (5.0, 100.0), (10.0, 0.0)], 10);
+        let buckets = resample(&data, 10);
+        assert_eq!(buckets.len(), 10);
+        assert!(buckets.iter().any(|&b| b == 100.0));
+    }
+
+    #[test]
+    fn render_sparkline_of_empty_data_is_empty() {
+        let data = data(Vec::new(), 0);
+        assert!(render_sparkline(&data).is_empty());
+    }
+
+    #[test]
+    fn render_sparkline_reports_peak_and_steady_state() {
+        let data = data(vec![(0.0, 0.0), (5.0, 100.0), (10.0, 50.0)], 10);
+        let line = render_sparkline(&data);
+        assert!(line.contains("peak"));
+        assert!(line.contains("end"));
+    }
+}
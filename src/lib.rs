@@ -7,12 +7,16 @@ use thiserror::Error;
 
 mod alloc;
 mod bench;
+mod export;
+mod graph;
 pub mod misc;
 pub mod parsers;
+mod platform;
 
 pub use alloc::TracingAlloc;
 pub use bench::Bench;
 use bench::{simple::run_simple_bench, AlternateAnswer, BenchEvent, Function, MemoryBenchError};
+use export::OutputFormat;
 
 static ARGS: Lazy<Args> = Lazy::new(Args::from_args);
 
@@ -38,6 +42,9 @@ pub enum BenchError {
 
     #[error("Day {} not defined", .0)]
     DaysFilterError(u8),
+
+    #[error("--output/--output-file/--baseline are only supported by the `detailed` subcommand")]
+    DetailedOnlyOption,
 }
 
 #[allow(non_snake_case)]
@@ -107,6 +114,31 @@ pub(crate) struct Args {
     #[structopt(long = "threads")]
     /// How many worker threads to spawn for benchmarking [default: cores - 2, min: 1]
     num_threads: Option<usize>,
+
+    #[structopt(long, parse(from_os_str))]
+    /// Directory to dump per-day/part SVG memory timelines to, when running `detailed`
+    graph_dir: Option<std::path::PathBuf>,
+
+    #[structopt(long)]
+    /// Export benchmark results (answer, timing distribution, allocations, peak memory)
+    /// as `json` or `csv`, for regression tracking in CI. Only supported when running
+    /// `detailed`.
+    output: Option<OutputFormat>,
+
+    #[structopt(long, parse(from_os_str))]
+    /// File to write the `--output` export to [default: stdout]. Only supported when
+    /// running `detailed`.
+    output_file: Option<std::path::PathBuf>,
+
+    #[structopt(long, parse(from_os_str))]
+    /// Prior export written with `--output json` to diff this run's results against. Only
+    /// supported when running `detailed`.
+    baseline: Option<std::path::PathBuf>,
+
+    #[structopt(long, default_value = "5.0")]
+    /// Percent change in time or peak memory beyond which `--baseline` flags a regression.
+    /// Only supported when running `detailed`.
+    regression_threshold: f64,
 }
 
 pub struct ProblemInput;
@@ -243,6 +275,25 @@ pub(crate) fn render_decimal(val: usize) -> String {
     )
 }
 
+/// An opaque identity function that stops the optimizer from treating its argument (or
+/// the call itself) as dead code, mirroring the `black_box` primitive used by benchmark
+/// harnesses like `bencher`/Criterion. Route a timing loop's output (and any input it was
+/// computed from) through this so a cheap, pure solution can't be hoisted out of the loop
+/// or eliminated entirely.
+#[inline(never)]
+pub fn black_box<T>(val: T) -> T {
+    // SAFETY: `read_volatile` forces an actual load of `val`, which the optimizer can't
+    // prove dead, and the loaded copy is what we return. `val` itself is then
+    // forgotten rather than dropped, since `ret` is the bitwise copy that now owns its
+    // resources. The fence stops the load from being reordered across the call boundary.
+    unsafe {
+        let ret = std::ptr::read_volatile(&val);
+        std::mem::forget(val);
+        std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+        ret
+    }
+}
+
 pub fn render_duration(time: Duration) -> String {
     // The logic here is basically copied from Criterion.
     let time = time.as_nanos() as f64;
@@ -274,7 +325,7 @@ pub fn render_duration(time: Duration) -> String {
     format!("{:>5.prec$} {}", time, unit, prec = prec)
 }
 
-fn print_header() {
+pub(crate) fn print_header() {
     if ARGS.run_type.is_run_only() {
         println!("   Day | {:<30} ", "Answer");
         println!("_______|_{0:_<30}", "");
@@ -286,14 +337,17 @@ fn print_header() {
         println!("_______|_{0:_<30}_|_{0:_<10}_|______________", "");
     } else {
         println!(
-            "   Day | {:<30} | {:<32} | Allocs  | Max Mem.",
+            "   Day | {:<30} | {:<32} | Allocs  | Max Mem. | Memory Timeline",
             "Answer", "Time"
         );
-        println!("_______|_{0:_<30}_|_{0:_<32}_|_________|_____________", "");
+        println!(
+            "_______|_{0:_<30}_|_{0:_<32}_|_________|_____________|_________________",
+            ""
+        );
     }
 }
 
-fn print_footer(total_time: Duration) {
+pub(crate) fn print_footer(total_time: Duration) {
     if ARGS.run_type.is_run_only() {
         println!("_______|_{0:_<30}", "");
     } else if let RunType::Bench {
@@ -305,7 +359,10 @@ fn print_footer(total_time: Duration) {
         println!(" Total Time: {:26} | {}", "", time);
     } else {
         let time = render_duration(total_time);
-        println!("_______|_{0:_<30}_|_{0:_<32}_|_________|_____________", "");
+        println!(
+            "_______|_{0:_<30}_|_{0:_<32}_|_________|_____________|_________________",
+            ""
+        );
         println!(" Total Time: {:26} | {}", "", time);
     }
 }
@@ -362,12 +419,37 @@ fn run_single(alloc: &'static TracingAlloc, year: u16, day: &Day) -> Result<(),
 }
 
 pub fn run(alloc: &'static TracingAlloc, year: u16, days: &[Day]) -> Result<(), BenchError> {
+    platform::raise_fd_limit();
+
     let days = get_days(days, ARGS.run_type.days())?;
 
+    let wants_detailed_only_options =
+        ARGS.output.is_some() || ARGS.output_file.is_some() || ARGS.baseline.is_some();
+    if wants_detailed_only_options && !matches!(ARGS.run_type, RunType::Detailed { .. }) {
+        return Err(BenchError::DetailedOnlyOption);
+    }
+
+    let baseline = ARGS
+        .baseline
+        .as_deref()
+        .map(export::load_baseline)
+        .transpose()
+        .map_err(UserError)?;
+
     println!("Advent of Code {}", year);
     match (&ARGS.run_type, &*days) {
         (RunType::Run { .. }, [day]) => run_single(alloc, year, day),
-        (RunType::Detailed { .. }, _) => todo!(),
+        (RunType::Detailed { .. }, days) => bench::detailed::run_detailed_bench(
+            alloc,
+            year,
+            days,
+            ARGS.bench_time,
+            ARGS.graph_dir.as_deref(),
+            ARGS.output,
+            ARGS.output_file.as_deref(),
+            baseline.as_deref(),
+            ARGS.regression_threshold,
+        ),
         (RunType::Run { .. } | RunType::Bench { .. }, days) => run_simple_bench(alloc, year, days),
     }
 }
@@ -0,0 +1,37 @@
+//! Platform-specific startup tweaks, kept out of the benchmarking logic itself.
+
+/// Bumps the soft `RLIMIT_NOFILE` toward the hard limit, so benchmarking many days
+/// across several worker threads doesn't exhaust the open-fd count each
+/// `bench_function_memory` call needs for its `tempfile::tempfile()` trace file.
+/// No-op on platforms without rlimits.
+#[cfg(unix)]
+pub(crate) fn raise_fd_limit() {
+    unsafe {
+        let mut limits = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+
+        if libc::getrlimit(libc::RLIMIT_NOFILE, &mut limits) != 0 {
+            return;
+        }
+
+        // macOS reports `rlim_max` as effectively unbounded but silently refuses to raise
+        // the soft limit past `OPEN_MAX`, so cap the target there instead of failing.
+        #[cfg(target_os = "macos")]
+        let target = limits.rlim_max.min(libc::OPEN_MAX as libc::rlim_t);
+        #[cfg(not(target_os = "macos"))]
+        let target = limits.rlim_max;
+
+        if target <= limits.rlim_cur {
+            return;
+        }
+
+        limits.rlim_cur = target;
+        // Best-effort: if the kernel refuses, we just keep running with the old limit.
+        libc::setrlimit(libc::RLIMIT_NOFILE, &limits);
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn raise_fd_limit() {}